@@ -1,9 +1,14 @@
 use colored::*;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::fs;
 use std::io::{self, Write};
+use std::str::FromStr;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 enum Suit {
     Hearts,
     Diamonds,
@@ -26,7 +31,21 @@ impl std::fmt::Display for Suit {
     }
 }
 
-#[derive(Debug)]
+impl FromStr for Suit {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "H" => Ok(Suit::Hearts),
+            "D" => Ok(Suit::Diamonds),
+            "C" => Ok(Suit::Clubs),
+            "S" => Ok(Suit::Spades),
+            _ => Err("Invalid suit"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Card {
     value: u8,
     suit: Suit,
@@ -55,27 +74,163 @@ impl std::fmt::Display for Card {
     }
 }
 
+impl FromStr for Card {
+    type Err = &'static str;
+
+    /// Parses a rank followed by a suit letter, e.g. `"AH"`, `"10S"`, `"QC"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() < 2 {
+            return Err("Invalid card string");
+        }
+        let (rank, suit) = s.split_at(s.len() - 1);
+        let value = match rank.to_uppercase().as_str() {
+            "A" => 1,
+            "J" => 11,
+            "Q" => 12,
+            "K" => 13,
+            _ => rank.parse().map_err(|_| "Invalid card rank")?,
+        };
+        Card::new(value, suit.parse()?)
+    }
+}
+
+const DEFAULT_NUM_DECKS: u32 = 6;
+const DEFAULT_PENETRATION: f64 = 0.25;
+
+/// The Hi-Lo count weight of a card: +1 for low cards, 0 for neutral
+/// middle cards, -1 for tens and aces.
+fn hi_lo_weight(card: &Card) -> i32 {
+    match card.value {
+        2..=6 => 1,
+        7..=9 => 0,
+        _ => -1,
+    }
+}
+
+/// A shoe of one or more standard 52-card decks. Tracks how many decks it
+/// was built from so it knows when it has been dealt down past its
+/// penetration threshold and needs to be rebuilt and reshuffled, and keeps
+/// a running Hi-Lo count of the cards that have left the shoe. Shuffles are
+/// drawn from a seeded RNG so a given seed always reproduces the same shoe.
 struct Deck {
     cards: Vec<Card>,
+    num_decks: u32,
+    penetration: f64,
+    running_count: i32,
+    rng: StdRng,
+    /// How many times this shoe has been shuffled, so a resumed session can
+    /// advance its seed past shuffles that already happened rather than
+    /// replaying them.
+    shuffles: u32,
 }
 
 impl Deck {
-    pub fn new() -> Deck {
-        let mut cards = Vec::new();
-        for suit in [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades] {
-            for value in 1..=13 {
-                cards.push(Card::new(value, suit).unwrap());
+    pub fn new(num_decks: u32, seed: u64) -> Deck {
+        Deck::with_penetration(num_decks, DEFAULT_PENETRATION, seed)
+    }
+
+    pub fn with_penetration(num_decks: u32, penetration: f64, seed: u64) -> Deck {
+        let mut deck = Deck {
+            cards: Vec::new(),
+            num_decks: num_decks.max(1),
+            penetration,
+            running_count: 0,
+            rng: StdRng::seed_from_u64(seed),
+            shuffles: 0,
+        };
+        deck.refill();
+        deck
+    }
+
+    /// Builds a deck that draws the given cards in order, so deterministic
+    /// scenarios can be set up for tests. The cut-card penetration is
+    /// nominal since such a deck is not meant to be reshuffled mid-session.
+    #[cfg(test)]
+    fn from_cards(mut cards: Vec<Card>, seed: u64) -> Deck {
+        cards.reverse();
+        Deck {
+            num_decks: 1,
+            penetration: DEFAULT_PENETRATION,
+            running_count: 0,
+            rng: StdRng::seed_from_u64(seed),
+            shuffles: 0,
+            cards,
+        }
+    }
+
+    /// Rebuilds a shoe from a saved snapshot of its remaining cards, exactly
+    /// as they were (next card to draw last), so a resumed session
+    /// continues the shoe where it left off. The RNG is reseeded past the
+    /// shuffles already done, so the next reshuffle doesn't replay one the
+    /// session already saw.
+    #[allow(clippy::too_many_arguments)]
+    pub fn resume(
+        cards: Vec<Card>,
+        num_decks: u32,
+        penetration: f64,
+        seed: u64,
+        running_count: i32,
+        shuffles: u32,
+    ) -> Deck {
+        Deck {
+            cards,
+            num_decks,
+            penetration,
+            running_count,
+            rng: StdRng::seed_from_u64(seed.wrapping_add(shuffles as u64)),
+            shuffles,
+        }
+    }
+
+    fn refill(&mut self) {
+        self.cards.clear();
+        self.running_count = 0;
+        for _ in 0..self.num_decks {
+            for suit in [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades] {
+                for value in 1..=13 {
+                    self.cards.push(Card::new(value, suit).unwrap());
+                }
             }
         }
-        Deck { cards }
     }
 
     pub fn shuffle(&mut self) {
-        self.cards.shuffle(&mut rand::thread_rng());
+        self.cards.shuffle(&mut self.rng);
+        self.shuffles += 1;
+    }
+
+    /// True once the cut-card has been reached, i.e. fewer cards remain
+    /// than `penetration` of the full shoe.
+    pub fn needs_shuffle(&self) -> bool {
+        let full_shoe = self.num_decks as usize * 52;
+        (self.cards.len() as f64) < (full_shoe as f64 * self.penetration)
+    }
+
+    /// Rebuilds the shoe from scratch and shuffles it. Should only be
+    /// called between hands, never mid-hand.
+    pub fn reshuffle(&mut self) {
+        self.refill();
+        self.shuffle();
     }
 
     pub fn draw(&mut self) -> Option<Card> {
-        self.cards.pop()
+        let card = self.cards.pop();
+        if let Some(card) = &card {
+            self.running_count += hi_lo_weight(card);
+        }
+        card
+    }
+
+    /// Decks still left in the shoe, rounded to the nearest half-deck.
+    pub fn decks_remaining(&self) -> f64 {
+        let raw = self.cards.len() as f64 / 52.0;
+        (raw * 2.0).round() / 2.0
+    }
+
+    /// The Hi-Lo true count: the running count normalized by how many
+    /// decks remain in the shoe.
+    pub fn true_count(&self) -> f64 {
+        self.running_count as f64 / self.decks_remaining().max(0.5)
     }
 }
 
@@ -156,94 +311,619 @@ fn print_hand(hand: &Vec<Card>) {
     println!();
 }
 
-fn main() {
-    println!("{}", "$$$$$$$$$$$$$$$$$$$$$".green());
-    println!("Welcome to Blackjack!");
-    println!("{}", "$$$$$$$$$$$$$$$$$$$$$".green());
-    println!();
+/// True for a natural blackjack: 21 on the original two cards, before any
+/// hit, double, or split.
+fn is_blackjack(cards: &Vec<Card>) -> bool {
+    cards.len() == 2 && hand_scores(cards).contains(&21)
+}
+
+/// One of the player's hands in progress, along with the bet riding on it.
+/// A round has one of these unless the player splits, in which case it has
+/// two.
+#[derive(Clone, Serialize, Deserialize)]
+struct PlayerHand {
+    cards: Vec<Card>,
+    bet: i32,
+    /// True for a hand that came from splitting a pair. Such a hand can
+    /// still total 21, but it isn't a natural blackjack, so it's excluded
+    /// from the 3:2 payout.
+    from_split: bool,
+}
+
+enum HandOutcome {
+    Blackjack,
+    Stood,
+    Busted,
+    Surrendered,
+}
+
+impl HandOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HandOutcome::Blackjack => "blackjack",
+            HandOutcome::Stood => "stood",
+            HandOutcome::Busted => "busted",
+            HandOutcome::Surrendered => "surrendered",
+        }
+    }
+}
+
+/// Settles one hand: prints the outcome and returns the total paid back to
+/// the player, including the original bet where applicable (0 covers a
+/// loss). `dealer_best_score` is the dealer's best score across their final
+/// hand, irrelevant for outcomes other than `Stood`.
+///
+/// Known simplification: a `Stood` hand only compares final scores, so a
+/// dealer natural 21 pushes against a player's non-natural 21 reached by
+/// drawing, rather than beating it as it would at a real table.
+fn settle_hand(hand: &PlayerHand, outcome: &HandOutcome, dealer_best_score: i8) -> i32 {
+    match outcome {
+        HandOutcome::Surrendered => {
+            println!("{}", "Surrendered.".yellow());
+            hand.bet / 2
+        }
+        HandOutcome::Busted => {
+            println!("{}", "You bust!".red());
+            0
+        }
+        HandOutcome::Blackjack => {
+            println!("{} {}!", "Blackjack!".green(), "You win!".green());
+            hand.bet + hand.bet * 3 / 2
+        }
+        HandOutcome::Stood => {
+            let player_best_score = *hand_scores(&hand.cards).iter().max().unwrap();
+            if dealer_best_score > 21 {
+                println!("Dealer busts! {}!", "You win!".green());
+                hand.bet * 2
+            } else if dealer_best_score > player_best_score {
+                println!("{}", "Dealer wins!".red());
+                0
+            } else if dealer_best_score < player_best_score {
+                println!("{}", "You win!".green());
+                hand.bet * 2
+            } else {
+                println!("{} Bet is returned.", "Push!".yellow());
+                hand.bet
+            }
+        }
+    }
+}
 
-    let mut deck = Deck::new();
-    deck.shuffle();
+/// A basic-strategy recommended action.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Recommendation {
+    Hit,
+    Stand,
+    Double,
+    Split,
+}
 
-    let mut money: i32 = 1000;
-    println!("You have {}", format!("${}", money).green());
+impl std::fmt::Display for Recommendation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Recommendation::Hit => "hit",
+                Recommendation::Stand => "stand",
+                Recommendation::Double => "double down",
+                Recommendation::Split => "split",
+            }
+        )
+    }
+}
+
+/// The dealer's upcard value for basic-strategy lookups: 2-10 at face
+/// value, ace counted as 11.
+fn dealer_upcard_value(card: &Card) -> u8 {
+    match card.value {
+        1 => 11,
+        11..=13 => 10,
+        value => value,
+    }
+}
+
+/// Whether a splittable pair should be split against the given dealer
+/// upcard, per standard basic strategy. Tens are never split and 5s are
+/// always played as a hard 10, so both defer to the hard/soft tables.
+fn pair_split_recommended(rank: u8, dealer: u8) -> bool {
+    match rank {
+        1 | 8 => true,
+        2 | 3 | 7 => (2..=7).contains(&dealer),
+        4 => dealer == 5 || dealer == 6,
+        6 => (2..=6).contains(&dealer),
+        9 => dealer != 7 && dealer < 10,
+        _ => false,
+    }
+}
+
+fn hard_recommendation(total: i8, dealer: u8, can_double: bool) -> Recommendation {
+    if total >= 17 {
+        Recommendation::Stand
+    } else if total == 12 {
+        if (4..=6).contains(&dealer) {
+            Recommendation::Stand
+        } else {
+            Recommendation::Hit
+        }
+    } else if (13..=16).contains(&total) {
+        if (2..=6).contains(&dealer) {
+            Recommendation::Stand
+        } else {
+            Recommendation::Hit
+        }
+    } else if can_double
+        && match total {
+            11 => dealer <= 10,
+            10 => dealer <= 9,
+            9 => (3..=6).contains(&dealer),
+            _ => false,
+        }
+    {
+        Recommendation::Double
+    } else {
+        Recommendation::Hit
+    }
+}
 
+fn soft_recommendation(total: i8, dealer: u8, can_double: bool) -> Recommendation {
+    if total >= 19 {
+        Recommendation::Stand
+    } else if total == 18 {
+        if dealer >= 9 {
+            Recommendation::Hit
+        } else if can_double && (3..=6).contains(&dealer) {
+            Recommendation::Double
+        } else {
+            Recommendation::Stand
+        }
+    } else if can_double
+        && match total {
+            13 | 14 => (5..=6).contains(&dealer),
+            15 | 16 => (4..=6).contains(&dealer),
+            17 => (3..=6).contains(&dealer),
+            _ => false,
+        }
+    {
+        Recommendation::Double
+    } else {
+        Recommendation::Hit
+    }
+}
+
+/// Recommends an action for the given hand against the dealer's upcard,
+/// per standard basic strategy. `can_double` and `can_split` suppress
+/// recommending an action that isn't actually on offer right now.
+fn basic_strategy_hint(
+    cards: &Vec<Card>,
+    dealer_upcard: &Card,
+    can_double: bool,
+    can_split: bool,
+) -> Recommendation {
+    let dealer = dealer_upcard_value(dealer_upcard);
+
+    if can_split
+        && cards.len() == 2
+        && cards[0].value == cards[1].value
+        && pair_split_recommended(cards[0].value, dealer)
+    {
+        return Recommendation::Split;
+    }
+
+    let scores = hand_scores(cards);
+    if scores.len() == 2 {
+        soft_recommendation(scores[1], dealer, can_double)
+    } else {
+        hard_recommendation(scores[0], dealer, can_double)
+    }
+}
+
+/// Runs the hit/stand decision loop for a single hand. Double down and
+/// surrender are only offered as the very first decision, matching how a
+/// real table treats them.
+fn player_turn(
+    hand: &mut PlayerHand,
+    deck: &mut Deck,
+    money: &mut i32,
+    dealer_upcard: &Card,
+    allow_double: bool,
+    allow_surrender: bool,
+    hint_mode: bool,
+) -> (HandOutcome, Vec<String>) {
+    let mut actions = Vec::new();
+
+    if !hand.from_split && is_blackjack(&hand.cards) {
+        return (HandOutcome::Blackjack, actions);
+    }
+
+    let mut first_decision = true;
     loop {
-        let bet = read_bet_amount(money);
-        money -= bet;
+        let scores = hand_scores(&hand.cards);
+        if scores.contains(&21) {
+            return (HandOutcome::Stood, actions);
+        }
+        if scores[0] > 21 {
+            return (HandOutcome::Busted, actions);
+        }
+
+        let can_double = first_decision && allow_double && *money >= hand.bet;
+        let can_surrender = first_decision && allow_surrender;
+
+        let mut prompt = String::from("[h]it or [s]tand");
+        if can_double {
+            prompt.push_str(", [d]ouble down");
+        }
+        if can_surrender {
+            prompt.push_str(", su[r]render");
+        }
+        prompt.push('?');
+
+        if hint_mode {
+            let hint = basic_strategy_hint(&hand.cards, dealer_upcard, can_double, false);
+            println!("(hint: {})", hint);
+        }
+
+        let answer = ask(&prompt).unwrap().to_lowercase();
+        if can_double && answer.starts_with('d') {
+            actions.push("double".to_string());
+            *money -= hand.bet;
+            hand.bet *= 2;
+            hand.cards.push(deck.draw().unwrap());
+            print!("You: ");
+            print_hand(&hand.cards);
+            let outcome = if hand_scores(&hand.cards)[0] > 21 {
+                HandOutcome::Busted
+            } else {
+                HandOutcome::Stood
+            };
+            return (outcome, actions);
+        } else if can_surrender && answer.starts_with('r') {
+            actions.push("surrender".to_string());
+            return (HandOutcome::Surrendered, actions);
+        } else if answer.starts_with('h') {
+            actions.push("hit".to_string());
+            hand.cards.push(deck.draw().unwrap());
+            print!("You: ");
+            print_hand(&hand.cards);
+            first_decision = false;
+        } else if answer.starts_with('s') {
+            actions.push("stand".to_string());
+            return (HandOutcome::Stood, actions);
+        } else {
+            first_decision = false;
+        }
+    }
+}
 
-        let mut player_hand = Vec::new();
+/// Command-line configuration for a session.
+struct Args {
+    num_decks: u32,
+    /// When set, the running and true Hi-Lo count are printed after each
+    /// hand, for players practicing card counting.
+    counter_mode: bool,
+    /// When set, a basic-strategy recommendation is printed at each
+    /// decision point, without forcing the choice.
+    hint_mode: bool,
+    /// Seeds the shoe's shuffles so a session can be reproduced exactly.
+    seed: u64,
+}
+
+/// Parses the number of decks, `--count`/`--hint` flags, and `--seed=N`
+/// from the CLI arguments. With no `--seed`, falls back to the
+/// `BLACKJACK_SEED` env var, then to a randomly generated seed.
+fn parse_args() -> Args {
+    let mut num_decks = DEFAULT_NUM_DECKS;
+    let mut counter_mode = false;
+    let mut hint_mode = false;
+    let mut seed = None;
+
+    for arg in std::env::args().skip(1) {
+        if arg == "--count" || arg == "-c" {
+            counter_mode = true;
+        } else if arg == "--hint" || arg == "-H" {
+            hint_mode = true;
+        } else if let Some(value) = arg.strip_prefix("--seed=") {
+            seed = value.parse().ok();
+        } else if let Ok(n) = arg.parse() {
+            num_decks = n;
+        }
+    }
+
+    let seed = seed
+        .or_else(|| std::env::var("BLACKJACK_SEED").ok()?.parse().ok())
+        .unwrap_or_else(rand::random);
+
+    Args {
+        num_decks,
+        counter_mode,
+        hint_mode,
+        seed,
+    }
+}
+
+const SAVE_FILE: &str = "blackjack_save.json";
+const ROUND_LOG_FILE: &str = "blackjack_rounds.jsonl";
+
+/// Everything needed to resume a session exactly where it left off: the
+/// player's money, the shoe's configuration and remaining contents, and
+/// the seed it was originally dealt from.
+#[derive(Serialize, Deserialize)]
+struct GameState {
+    money: i32,
+    num_decks: u32,
+    penetration: f64,
+    seed: u64,
+    shoe: Vec<Card>,
+    running_count: i32,
+    shuffles: u32,
+}
+
+/// A single hand's outcome, logged as part of a completed round.
+#[derive(Serialize)]
+struct HandLog {
+    initial_cards: Vec<Card>,
+    actions: Vec<String>,
+    final_cards: Vec<Card>,
+    outcome: &'static str,
+    payout: i32,
+}
+
+/// One completed round, appended to `ROUND_LOG_FILE` as a JSON line so
+/// sessions can be analyzed or replayed afterward.
+#[derive(Serialize)]
+struct RoundLog {
+    bet: i32,
+    dealer_upcard: Card,
+    dealer_final_hand: Vec<Card>,
+    hands: Vec<HandLog>,
+}
+
+/// The live session: the persisted `GameState` plus runtime-only display
+/// settings that aren't part of the saved game itself.
+struct Game {
+    money: i32,
+    deck: Deck,
+    seed: u64,
+    hint_mode: bool,
+    counter_mode: bool,
+}
+
+impl Game {
+    fn new(args: &Args) -> Game {
+        let mut deck = Deck::new(args.num_decks, args.seed);
+        deck.shuffle();
+        Game {
+            money: 1000,
+            deck,
+            seed: args.seed,
+            hint_mode: args.hint_mode,
+            counter_mode: args.counter_mode,
+        }
+    }
+
+    fn from_state(state: GameState, args: &Args) -> Game {
+        let deck = Deck::resume(
+            state.shoe,
+            state.num_decks,
+            state.penetration,
+            state.seed,
+            state.running_count,
+            state.shuffles,
+        );
+        Game {
+            money: state.money,
+            deck,
+            seed: state.seed,
+            hint_mode: args.hint_mode,
+            counter_mode: args.counter_mode,
+        }
+    }
+
+    fn to_state(&self) -> GameState {
+        GameState {
+            money: self.money,
+            num_decks: self.deck.num_decks,
+            penetration: self.deck.penetration,
+            seed: self.seed,
+            shoe: self.deck.cards.clone(),
+            running_count: self.deck.running_count,
+            shuffles: self.deck.shuffles,
+        }
+    }
+
+    /// Writes the current session to `SAVE_FILE` so it can be resumed later.
+    fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.to_state())?;
+        fs::write(SAVE_FILE, json)
+    }
+
+    /// Loads a previously saved session, if one exists.
+    fn load_state() -> Option<GameState> {
+        let json = fs::read_to_string(SAVE_FILE).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Appends a completed round to `ROUND_LOG_FILE` as a single JSON line.
+    fn log_round(round: &RoundLog) -> io::Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(ROUND_LOG_FILE)?;
+        writeln!(file, "{}", serde_json::to_string(round)?)
+    }
+
+    /// Plays a single round: betting, dealing, the player's and dealer's
+    /// turns, and settling payouts. Returns a log of what happened.
+    fn play_round(&mut self) -> RoundLog {
+        if self.deck.needs_shuffle() {
+            println!("{}", "Shuffling the shoe".yellow());
+            self.deck.reshuffle();
+        }
+
+        let bet = read_bet_amount(self.money);
+        self.money -= bet;
+
+        let mut player_hand = PlayerHand {
+            cards: Vec::new(),
+            bet,
+            from_split: false,
+        };
         let mut dealer_hand = Vec::new();
 
-        player_hand.push(deck.draw().unwrap());
-        dealer_hand.push(deck.draw().unwrap());
+        player_hand.cards.push(self.deck.draw().unwrap());
+        dealer_hand.push(self.deck.draw().unwrap());
 
-        player_hand.push(deck.draw().unwrap());
-        dealer_hand.push(deck.draw().unwrap());
+        player_hand.cards.push(self.deck.draw().unwrap());
+        dealer_hand.push(self.deck.draw().unwrap());
 
         println!("Dealer: {} ??", dealer_hand[0]);
         print!("You: ");
-        print_hand(&player_hand);
+        print_hand(&player_hand.cards);
 
-        let mut player_scores = hand_scores(&player_hand);
-        let mut dealer_scores = hand_scores(&dealer_hand);
+        let can_split = player_hand.cards[0].value == player_hand.cards[1].value
+            && self.money >= bet
+            && !is_blackjack(&player_hand.cards);
 
-        while player_scores[0] < 21 {
-            if player_scores.iter().any(|&score| score == 21) {
-                break;
-            }
-            let answer = ask("[h]it or [s]tand?").unwrap().to_lowercase();
-            if answer.starts_with('h') {
-                player_hand.push(deck.draw().unwrap());
-                print_hand(&player_hand);
-                player_scores = hand_scores(&player_hand);
-            } else if answer.starts_with('s') {
-                break;
+        if can_split && self.hint_mode {
+            let hint = basic_strategy_hint(&player_hand.cards, &dealer_hand[0], true, true);
+            println!("(hint: {})", hint);
+        }
+
+        let mut hands = vec![player_hand];
+        if can_split && confirm("Split your hand?") {
+            let mut hand1 = hands.pop().unwrap();
+            let split_card = hand1.cards.pop().unwrap();
+            hand1.from_split = true;
+            self.money -= bet;
+            let mut hand2 = PlayerHand {
+                cards: vec![split_card],
+                bet,
+                from_split: true,
+            };
+            hand1.cards.push(self.deck.draw().unwrap());
+            hand2.cards.push(self.deck.draw().unwrap());
+            hands.push(hand1);
+            hands.push(hand2);
+        }
+
+        let split = hands.len() > 1;
+        let mut results = Vec::new();
+        for (i, mut hand) in hands.into_iter().enumerate() {
+            if split {
+                println!("\nHand {}: ", i + 1);
+                print!("You: ");
+                print_hand(&hand.cards);
             }
+            let initial_cards = hand.cards.clone();
+            let (outcome, actions) = player_turn(
+                &mut hand,
+                &mut self.deck,
+                &mut self.money,
+                &dealer_hand[0],
+                true,
+                !split,
+                self.hint_mode,
+            );
+            results.push((hand, outcome, initial_cards, actions));
         }
 
-        if player_scores.iter().all(|&score| score > 21) {
-            println!("{}", "You bust!".red());
-        } else {
-            // Dealer's Play
-            println!("\nDealer's Play");
+        let any_active = results
+            .iter()
+            .any(|(_, outcome, ..)| matches!(outcome, HandOutcome::Stood | HandOutcome::Blackjack));
 
+        let mut dealer_scores = hand_scores(&dealer_hand);
+        if any_active {
+            println!("\nDealer's Play");
             while *dealer_scores.iter().max().unwrap() < 17 {
-                dealer_hand.push(deck.draw().unwrap());
+                dealer_hand.push(self.deck.draw().unwrap());
                 dealer_scores = hand_scores(&dealer_hand);
             }
-            let dealer_best_score = *dealer_scores.iter().max().unwrap();
             print!("Dealer: ");
             print_hand(&dealer_hand);
+        }
+        let dealer_best_score = *dealer_scores.iter().max().unwrap();
 
-            let player_best_score = *player_scores.iter().max().unwrap();
-
-            if dealer_best_score > 21 {
-                println!("Dealer busts! {}!", "You win!".green());
-                money += bet * 3 / 2;
-            } else if dealer_best_score > player_best_score {
-                println!("{}", "Dealer wins!".red());
-            } else if dealer_best_score < player_best_score {
-                println!("{}", "You win!".green());
-                money += bet * 3 / 2;
-            } else {
-                println!("{} Bet is returned.", "Push!".yellow());
-                money += bet;
+        let mut hand_logs = Vec::new();
+        for (hand, outcome, initial_cards, actions) in results {
+            if split {
+                print!("Hand: ");
+                print_hand(&hand.cards);
             }
+            let payout = settle_hand(&hand, &outcome, dealer_best_score);
+            self.money += payout;
+            hand_logs.push(HandLog {
+                initial_cards,
+                actions,
+                final_cards: hand.cards,
+                outcome: outcome.as_str(),
+                payout: payout - hand.bet,
+            });
         }
 
-        if money == 0 {
+        if self.counter_mode {
+            println!(
+                "Running count: {} | True count: {:.1}",
+                self.deck.running_count,
+                self.deck.true_count()
+            );
+        }
+
+        RoundLog {
+            bet,
+            dealer_upcard: dealer_hand[0].clone(),
+            dealer_final_hand: dealer_hand,
+            hands: hand_logs,
+        }
+    }
+
+    /// Runs rounds until the player goes broke or quits, logging each
+    /// round and saving the session on the way out.
+    fn run(&mut self) {
+        if self.money == 0 {
             println!("{}", "You're broke! Goodbye!".red().bold());
-            break;
+        } else {
+            println!("You have {}", format!("${}", self.money).green());
+
+            loop {
+                let round = self.play_round();
+                if let Err(err) = Game::log_round(&round) {
+                    eprintln!("Warning: couldn't write round log: {}", err);
+                }
+
+                if self.money == 0 {
+                    println!("{}", "You're broke! Goodbye!".red().bold());
+                    break;
+                }
+                println!("\nYou have ${}", format!("{}", self.money).green());
+                if !confirm("Do you want to continue?") {
+                    println!("Thanks for playing!");
+                    break;
+                }
+            }
         }
-        println!("\nYou have ${}", format!("{}", money).green());
-        if !confirm("Do you want to continue?") {
-            println!("Thanks for playing!");
-            break;
+
+        if let Err(err) = self.save() {
+            eprintln!("Warning: couldn't save session: {}", err);
         }
     }
 }
 
+fn main() {
+    println!("{}", "$$$$$$$$$$$$$$$$$$$$$".green());
+    println!("Welcome to Blackjack!");
+    println!("{}", "$$$$$$$$$$$$$$$$$$$$$".green());
+    println!();
+
+    let args = parse_args();
+
+    let mut game = match Game::load_state() {
+        Some(state) if confirm("Resume your saved game?") => Game::from_state(state, &args),
+        _ => Game::new(&args),
+    };
+
+    game.run();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,4 +980,134 @@ mod tests {
         ];
         assert_eq!(hand_scores(&hand), vec![20]);
     }
+
+    #[test]
+    fn test_card_from_str() {
+        let card: Card = "AH".parse().unwrap();
+        assert_eq!(card.value, 1);
+        assert!(matches!(card.suit, Suit::Hearts));
+
+        let card: Card = "10s".parse().unwrap();
+        assert_eq!(card.value, 10);
+        assert!(matches!(card.suit, Suit::Spades));
+
+        let card: Card = "QC".parse().unwrap();
+        assert_eq!(card.value, 12);
+        assert!(matches!(card.suit, Suit::Clubs));
+    }
+
+    #[test]
+    fn test_card_from_str_invalid() {
+        assert!("".parse::<Card>().is_err());
+        assert!("14H".parse::<Card>().is_err());
+        assert!("5X".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn test_deck_from_cards_draws_in_order() {
+        let mut deck = Deck::from_cards(
+            vec!["AH".parse().unwrap(), "10S".parse().unwrap()],
+            42,
+        );
+        assert_eq!(deck.draw().unwrap().to_string(), "A♥");
+        assert_eq!(deck.draw().unwrap().to_string(), "10♠");
+        assert!(deck.draw().is_none());
+    }
+
+    #[test]
+    fn test_natural_blackjack_pays_three_to_two() {
+        let mut hand = PlayerHand {
+            cards: vec!["AH".parse().unwrap(), "KS".parse().unwrap()],
+            bet: 10,
+            from_split: false,
+        };
+        let mut deck = Deck::from_cards(vec![], 1);
+        let mut money = 100;
+        let dealer_upcard: Card = "7H".parse().unwrap();
+        let (outcome, _) =
+            player_turn(&mut hand, &mut deck, &mut money, &dealer_upcard, false, false, false);
+        assert!(matches!(outcome, HandOutcome::Blackjack));
+        assert_eq!(settle_hand(&hand, &outcome, 17), 25);
+    }
+
+    #[test]
+    fn test_split_hand_with_21_is_not_natural_blackjack() {
+        let mut hand = PlayerHand {
+            cards: vec!["AH".parse().unwrap(), "KS".parse().unwrap()],
+            bet: 10,
+            from_split: true,
+        };
+        let mut deck = Deck::from_cards(vec![], 1);
+        let mut money = 100;
+        let dealer_upcard: Card = "7H".parse().unwrap();
+        let (outcome, _) =
+            player_turn(&mut hand, &mut deck, &mut money, &dealer_upcard, false, false, false);
+        assert!(matches!(outcome, HandOutcome::Stood));
+        // Even money, not the 3:2 blackjack payout.
+        assert_eq!(settle_hand(&hand, &outcome, 17), 20);
+    }
+
+    #[test]
+    fn test_double_down_payout() {
+        // An already-doubled bet (original 20, doubled to 40) on a winning hand.
+        let hand = PlayerHand {
+            cards: vec!["9H".parse().unwrap(), "2S".parse().unwrap(), "KS".parse().unwrap()],
+            bet: 40,
+            from_split: false,
+        };
+        assert_eq!(settle_hand(&hand, &HandOutcome::Stood, 17), 80);
+    }
+
+    #[test]
+    fn test_surrender_payout() {
+        let hand = PlayerHand {
+            cards: vec!["9H".parse().unwrap(), "7S".parse().unwrap()],
+            bet: 20,
+            from_split: false,
+        };
+        assert_eq!(settle_hand(&hand, &HandOutcome::Surrendered, 17), 10);
+    }
+
+    #[test]
+    fn test_split_hands_settle_independently() {
+        let winning = PlayerHand {
+            cards: vec!["10H".parse().unwrap(), "9S".parse().unwrap()],
+            bet: 10,
+            from_split: true,
+        };
+        let busted = PlayerHand {
+            cards: vec!["10C".parse().unwrap(), "KC".parse().unwrap(), "5D".parse().unwrap()],
+            bet: 10,
+            from_split: true,
+        };
+        assert_eq!(settle_hand(&winning, &HandOutcome::Stood, 17), 20);
+        assert_eq!(settle_hand(&busted, &HandOutcome::Busted, 17), 0);
+    }
+
+    #[test]
+    fn test_game_state_save_load_round_trip() {
+        let state = GameState {
+            money: 850,
+            num_decks: 6,
+            penetration: 0.25,
+            seed: 42,
+            shoe: vec!["AH".parse().unwrap(), "10S".parse().unwrap()],
+            running_count: -3,
+            shuffles: 2,
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        let restored: GameState = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.money, state.money);
+        assert_eq!(restored.num_decks, state.num_decks);
+        assert_eq!(restored.penetration, state.penetration);
+        assert_eq!(restored.seed, state.seed);
+        assert_eq!(restored.running_count, state.running_count);
+        assert_eq!(restored.shuffles, state.shuffles);
+        assert_eq!(
+            restored.shoe.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+            state.shoe.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+        );
+    }
 }